@@ -0,0 +1,167 @@
+//! Async execution engine for `ratel run`.
+//!
+//! STEPs are independent of each other, so they run concurrently under a
+//! `--jobs`-bounded semaphore. Within a STEP, actions still run in order:
+//! a `CHECK` reads the `expr::Context` the preceding `ATTACK` populated, so
+//! reordering them would break request evaluation. Every `ATTACK` is wrapped
+//! in a per-action timeout and races the shared `CancellationToken`, so a
+//! hung target degrades to an `ERROR` result instead of stalling the run.
+//! `CHECK`s are evaluated in place against the context and are not timed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+use crate::expr;
+use crate::headers;
+use crate::report::{ActionResult, StepResult};
+
+#[derive(Clone)]
+pub enum ActionSpec {
+    Attack(String),
+    Check(String),
+}
+
+pub struct StepSpec {
+    pub title: String,
+    pub actions: Vec<ActionSpec>,
+}
+
+pub struct EngineConfig {
+    pub jobs: usize,
+    pub timeout: Duration,
+}
+
+/// Runs every step concurrently (bounded by `config.jobs`) and collects
+/// results back into their original slots, regardless of completion order.
+pub async fn run_steps(
+    steps: Vec<StepSpec>,
+    config: &EngineConfig,
+    cancel: CancellationToken,
+) -> Vec<StepResult> {
+    let semaphore = Arc::new(Semaphore::new(config.jobs.max(1)));
+    let timeout = config.timeout;
+
+    let handles: Vec<_> = steps
+        .into_iter()
+        .map(|step| {
+            let semaphore = semaphore.clone();
+            let cancel = cancel.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("semaphore closed");
+                run_step(step, timeout, cancel).await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.expect("step task panicked"));
+    }
+    results
+}
+
+async fn run_step(step: StepSpec, timeout: Duration, cancel: CancellationToken) -> StepResult {
+    let mut ctx = expr::Context::default();
+    let mut results = Vec::with_capacity(step.actions.len());
+
+    for action in step.actions {
+        results.extend(run_action(action, timeout, &cancel, &mut ctx).await);
+    }
+
+    StepResult {
+        title: step.title,
+        results,
+    }
+}
+
+async fn run_action(
+    action: ActionSpec,
+    timeout: Duration,
+    cancel: &CancellationToken,
+    ctx: &mut expr::Context,
+) -> Vec<ActionResult> {
+    match action {
+        ActionSpec::Attack(value) => run_attack(value, timeout, cancel, ctx).await,
+        ActionSpec::Check(value) => vec![run_check(value, ctx)],
+    }
+}
+
+async fn run_attack(
+    value: String,
+    timeout: Duration,
+    cancel: &CancellationToken,
+    ctx: &mut expr::Context,
+) -> Vec<ActionResult> {
+    if cancel.is_cancelled() {
+        return vec![timeout_result("ATTACK", value, "audit was cancelled before this action ran")];
+    }
+
+    let task_value = value.clone();
+    let attack = tokio::task::spawn_blocking(move || cdd_core::execute_attack("attack", &task_value));
+
+    let outcome = tokio::select! {
+        _ = cancel.cancelled() => None,
+        res = tokio::time::timeout(timeout, attack) => res.ok().and_then(|r| r.ok()),
+    };
+
+    match outcome {
+        Some(core_res) => {
+            *ctx = expr::Context {
+                status: core_res.status,
+                headers: core_res.headers.clone(),
+                body: core_res.body.clone(),
+            };
+
+            // `secure_headers` is a graded, multi-row attack; every other
+            // attack still reports as a single pass/fail row.
+            if value == "secure_headers" {
+                headers::evaluate(ctx)
+            } else {
+                vec![ActionResult {
+                    kind: "ATTACK".into(),
+                    value,
+                    target: None,
+                    status: if core_res.success { "SUCCESS".into() } else { "FAILED".into() },
+                    message: core_res.message,
+                    grade: None,
+                }]
+            }
+        }
+        None => vec![timeout_result("ATTACK", value, "attack timed out or was cancelled")],
+    }
+}
+
+fn run_check(value: String, ctx: &expr::Context) -> ActionResult {
+    match expr::evaluate(&value, ctx) {
+        Ok(passed) => ActionResult {
+            kind: "CHECK".into(),
+            value,
+            target: None,
+            status: if passed { "SUCCESS".into() } else { "FAILED".into() },
+            message: String::new(),
+            grade: None,
+        },
+        Err(e) => ActionResult {
+            kind: "CHECK".into(),
+            value,
+            target: None,
+            status: "ERROR".into(),
+            message: e.to_string(),
+            grade: None,
+        },
+    }
+}
+
+fn timeout_result(kind: &str, value: String, message: &str) -> ActionResult {
+    ActionResult {
+        kind: kind.into(),
+        value,
+        target: None,
+        status: "ERROR".into(),
+        message: message.into(),
+        grade: None,
+    }
+}
@@ -0,0 +1,579 @@
+//! Expression language backing `CHECK` conditions.
+//!
+//! Three stages: [`tokenize`] turns the raw condition text into [`Token`]s,
+//! [`parse`] runs a recursive-descent parser over those tokens into an [`Expr`] tree, and
+//! [`eval`] walks the tree against a [`Context`] built from the response the
+//! executor just observed. Nothing here panics on malformed input or missing
+//! data; both surface as `Err(EvalError)` so the caller can fold them into an
+//! `ActionResult` with `status: "ERROR"`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Str(String),
+    Num(f64),
+    Ident(String),
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, EvalError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(EvalError(format!("unterminated string literal: \"{}", s)));
+                }
+                i += 1; // closing quote
+                tokens.push(Token::Str(s));
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Eq);
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Ne);
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Ge);
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Gt);
+                i += 1;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Le);
+                i += 2;
+            }
+            '<' => {
+                tokens.push(Token::Lt);
+                i += 1;
+            }
+            _ if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let n = text
+                    .parse::<f64>()
+                    .map_err(|_| EvalError(format!("invalid number literal: {}", text)))?;
+                tokens.push(Token::Num(n));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            other => return Err(EvalError(format!("unexpected character '{}'", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// AST
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnOp {
+    Not,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Var(String),
+    FnCall(String, Vec<Expr>),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+// ---------------------------------------------------------------------------
+// Parser (recursive descent, precedence OR < AND < NOT < comparison)
+//
+// NOT binds looser than comparison (including the infix `contains`/`matches`
+// forms) so `NOT body matches "password"` parses as `NOT (body matches
+// "password")`, matching the request-language examples, rather than
+// `(NOT body) matches "password"`.
+// ---------------------------------------------------------------------------
+
+pub fn parse(tokens: &[Token]) -> Result<Expr, EvalError> {
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(EvalError(format!(
+            "unexpected trailing tokens starting at {:?}",
+            parser.tokens[parser.pos]
+        )));
+    }
+    Ok(expr)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&Token> {
+        let t = self.tokens.get(self.pos);
+        self.pos += 1;
+        t
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, EvalError> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            let right = self.parse_and()?;
+            left = Expr::Binary(BinOp::Or, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, EvalError> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.advance();
+            let right = self.parse_not()?;
+            left = Expr::Binary(BinOp::And, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, EvalError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.advance();
+            let operand = self.parse_not()?;
+            return Ok(Expr::Unary(UnOp::Not, Box::new(operand)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, EvalError> {
+        let left = self.parse_primary()?;
+
+        // Infix form of a two-argument function, e.g. `header("X") contains "Y"`
+        // or `body matches "password"` — sugar for `contains(header("X"), "Y")`.
+        if let Some(Token::Ident(name)) = self.peek() {
+            if name == "contains" || name == "matches" {
+                let name = name.clone();
+                self.advance();
+                let right = self.parse_primary()?;
+                return Ok(Expr::FnCall(name, vec![left, right]));
+            }
+        }
+
+        let op = match self.peek() {
+            Some(Token::Eq) => BinOp::Eq,
+            Some(Token::Ne) => BinOp::Ne,
+            Some(Token::Gt) => BinOp::Gt,
+            Some(Token::Ge) => BinOp::Ge,
+            Some(Token::Lt) => BinOp::Lt,
+            Some(Token::Le) => BinOp::Le,
+            _ => return Ok(left),
+        };
+        self.advance();
+        let right = self.parse_primary()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, EvalError> {
+        match self.advance().cloned() {
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::Str(s))),
+            Some(Token::Num(n)) => Ok(Expr::Literal(Value::Num(n))),
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    other => Err(EvalError(format!("expected ')', found {:?}", other))),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if matches!(self.peek(), Some(Token::LParen)) {
+                    self.advance();
+                    let mut args = Vec::new();
+                    if !matches!(self.peek(), Some(Token::RParen)) {
+                        loop {
+                            args.push(self.parse_or()?);
+                            if matches!(self.peek(), Some(Token::Comma)) {
+                                self.advance();
+                                continue;
+                            }
+                            break;
+                        }
+                    }
+                    match self.advance() {
+                        Some(Token::RParen) => Ok(Expr::FnCall(name, args)),
+                        other => Err(EvalError(format!("expected ')', found {:?}", other))),
+                    }
+                } else {
+                    let mut path = name;
+                    while matches!(self.peek(), Some(Token::Dot)) {
+                        self.advance();
+                        match self.advance().cloned() {
+                            Some(Token::Ident(field)) => {
+                                path.push('.');
+                                path.push_str(&field);
+                            }
+                            other => {
+                                return Err(EvalError(format!(
+                                    "expected identifier after '.', found {:?}",
+                                    other
+                                )))
+                            }
+                        }
+                    }
+                    Ok(Expr::Var(path))
+                }
+            }
+            other => Err(EvalError(format!("unexpected token {:?}", other))),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Values, context and evaluation
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    None,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::None => write!(f, ""),
+        }
+    }
+}
+
+/// Response data captured after an `ATTACK` runs, consumed by `CHECK`.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub status: Option<u16>,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Context {
+    fn resolve(&self, path: &str) -> Value {
+        match path {
+            "response.status" => self
+                .status
+                .map(|s| Value::Num(s as f64))
+                .unwrap_or(Value::None),
+            "body" => Value::Str(self.body.clone()),
+            _ => Value::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalError(pub String);
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub fn eval(expr: &Expr, ctx: &Context) -> Result<Value, EvalError> {
+    match expr {
+        Expr::Literal(v) => Ok(v.clone()),
+        Expr::Var(name) => Ok(ctx.resolve(name)),
+        Expr::Unary(UnOp::Not, inner) => Ok(Value::Bool(!truthy(&eval(inner, ctx)?))),
+        Expr::Binary(op, left, right) => eval_binary(op, left, right, ctx),
+        Expr::FnCall(name, args) => {
+            let values = args
+                .iter()
+                .map(|a| eval(a, ctx))
+                .collect::<Result<Vec<_>, _>>()?;
+            call_fn(name, &values, ctx)
+        }
+    }
+}
+
+fn eval_binary(op: &BinOp, left: &Expr, right: &Expr, ctx: &Context) -> Result<Value, EvalError> {
+    if matches!(op, BinOp::And | BinOp::Or) {
+        let l = truthy(&eval(left, ctx)?);
+        return Ok(match op {
+            BinOp::And => Value::Bool(l && truthy(&eval(right, ctx)?)),
+            BinOp::Or => Value::Bool(l || truthy(&eval(right, ctx)?)),
+            _ => unreachable!(),
+        });
+    }
+
+    let l = eval(left, ctx)?;
+    let r = eval(right, ctx)?;
+    match op {
+        BinOp::Eq => Ok(Value::Bool(compare(&l, &r) == std::cmp::Ordering::Equal)),
+        BinOp::Ne => Ok(Value::Bool(compare(&l, &r) != std::cmp::Ordering::Equal)),
+        BinOp::Gt => Ok(Value::Bool(compare(&l, &r) == std::cmp::Ordering::Greater)),
+        BinOp::Ge => Ok(Value::Bool(compare(&l, &r) != std::cmp::Ordering::Less)),
+        BinOp::Lt => Ok(Value::Bool(compare(&l, &r) == std::cmp::Ordering::Less)),
+        BinOp::Le => Ok(Value::Bool(compare(&l, &r) != std::cmp::Ordering::Greater)),
+        BinOp::And | BinOp::Or => unreachable!(),
+    }
+}
+
+fn truthy(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::None => false,
+        Value::Str(s) => !s.is_empty(),
+        Value::Num(n) => *n != 0.0,
+    }
+}
+
+/// Compares two values, coercing a numeric string to a number when the other
+/// side is numeric so `response.status >= 200` behaves whether `200` arrived
+/// as a literal or as text pulled out of a header.
+fn compare(a: &Value, b: &Value) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    match (a, b) {
+        (Value::Num(x), Value::Num(y)) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+        (Value::Str(x), Value::Str(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::None, Value::None) => Ordering::Equal,
+        (Value::Num(x), Value::Str(y)) => match y.parse::<f64>() {
+            Ok(y) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            Err(_) => x.to_string().cmp(y),
+        },
+        (Value::Str(x), Value::Num(y)) => match x.parse::<f64>() {
+            Ok(x) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+            Err(_) => x.cmp(&y.to_string()),
+        },
+        _ => format!("{}", a).cmp(&format!("{}", b)),
+    }
+}
+
+fn call_fn(name: &str, args: &[Value], ctx: &Context) -> Result<Value, EvalError> {
+    match name {
+        "header" => {
+            let key = expect_str(args, 0, "header")?;
+            Ok(ctx
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| Value::Str(v.clone()))
+                .unwrap_or(Value::None))
+        }
+        "len" => {
+            let v = args
+                .first()
+                .ok_or_else(|| EvalError("len() expects 1 argument".into()))?;
+            Ok(Value::Num(format!("{}", v).len() as f64))
+        }
+        "lower" => {
+            let s = expect_str(args, 0, "lower")?;
+            Ok(Value::Str(s.to_lowercase()))
+        }
+        "contains" => {
+            let hay = expect_str(args, 0, "contains")?;
+            let needle = expect_str(args, 1, "contains")?;
+            Ok(Value::Bool(hay.contains(needle)))
+        }
+        "matches" => {
+            let text = expect_str(args, 0, "matches")?;
+            let pattern = expect_str(args, 1, "matches")?;
+            let re = regex::Regex::new(pattern)
+                .map_err(|e| EvalError(format!("invalid regex in matches(): {}", e)))?;
+            Ok(Value::Bool(re.is_match(text)))
+        }
+        other => Err(EvalError(format!("unknown function '{}'", other))),
+    }
+}
+
+fn expect_str<'a>(args: &'a [Value], idx: usize, fn_name: &str) -> Result<&'a str, EvalError> {
+    match args.get(idx) {
+        Some(Value::Str(s)) => Ok(s.as_str()),
+        Some(Value::None) | None => Err(EvalError(format!(
+            "{}() expects a string argument at position {}",
+            fn_name, idx
+        ))),
+        Some(other) => Err(EvalError(format!(
+            "{}() expects a string argument at position {}, got {}",
+            fn_name, idx, other
+        ))),
+    }
+}
+
+/// Tokenizes, parses and evaluates `condition` in one call — the entry point
+/// `engine::run_check` reaches for when handling a `CHECK`.
+pub fn evaluate(condition: &str, ctx: &Context) -> Result<bool, EvalError> {
+    let tokens = tokenize(condition)?;
+    let expr = parse(&tokens)?;
+    Ok(truthy(&eval(&expr, ctx)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx_with(status: u16, headers: &[(&str, &str)], body: &str) -> Context {
+        Context {
+            status: Some(status),
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn default_scenario_checks_pass_against_a_compliant_response() {
+        let ctx = ctx_with(
+            200,
+            &[("Strict-Transport-Security", "max-age=15552000; includeSubDomains")],
+            "",
+        );
+        assert_eq!(
+            evaluate(r#"header("Strict-Transport-Security") contains "max-age""#, &ctx),
+            Ok(true)
+        );
+        assert_eq!(evaluate("response.status == 200", &ctx), Ok(true));
+    }
+
+    #[test]
+    fn request_language_examples_evaluate_as_documented() {
+        let ctx = ctx_with(
+            204,
+            &[("Content-Security-Policy", "default-src 'self'")],
+            "ok",
+        );
+        assert_eq!(
+            evaluate("response.status >= 200 AND response.status < 300", &ctx),
+            Ok(true)
+        );
+        assert_eq!(
+            evaluate(r#"header("Content-Security-Policy") contains "default-src""#, &ctx),
+            Ok(true)
+        );
+        assert_eq!(evaluate(r#"NOT body matches "password""#, &ctx), Ok(true));
+    }
+
+    #[test]
+    fn not_binds_looser_than_the_infix_comparison_it_wraps() {
+        let ctx = ctx_with(200, &[], "contains a password");
+        // NOT (body matches "password"), not (NOT body) matches "password".
+        assert_eq!(evaluate(r#"NOT body matches "password""#, &ctx), Ok(false));
+    }
+
+    #[test]
+    fn missing_header_resolves_to_none_instead_of_panicking() {
+        let ctx = ctx_with(200, &[], "");
+        assert_eq!(evaluate(r#"header("X-Missing") == "anything""#, &ctx), Ok(false));
+        assert_eq!(evaluate(r#"len(header("X-Missing")) > 0"#, &ctx), Ok(false));
+    }
+
+    #[test]
+    fn numeric_and_string_comparisons_coerce_consistently() {
+        let ctx = ctx_with(200, &[("X-Count", "42")], "");
+        assert_eq!(evaluate(r#"header("X-Count") == 42"#, &ctx), Ok(true));
+        assert_eq!(evaluate(r#"42 == header("X-Count")"#, &ctx), Ok(true));
+    }
+
+    #[test]
+    fn ordered_comparisons_coerce_regardless_of_which_side_is_the_string() {
+        let ctx = ctx_with(200, &[("Retry-After", "30")], "");
+        assert_eq!(evaluate(r#"header("Retry-After") > 20"#, &ctx), Ok(true));
+        assert_eq!(evaluate(r#"20 < header("Retry-After")"#, &ctx), Ok(true));
+        assert_eq!(evaluate(r#"header("Retry-After") < 20"#, &ctx), Ok(false));
+    }
+
+    #[test]
+    fn malformed_input_errors_instead_of_panicking() {
+        let ctx = Context::default();
+        assert!(evaluate("response.status ===", &ctx).is_err());
+        assert!(evaluate("response.status ==", &ctx).is_err());
+        assert!(evaluate("(response.status == 200", &ctx).is_err());
+        assert!(evaluate(r#"contains("only one arg")"#, &ctx).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Built-in `secure_headers` attack: grades a response's hardening headers
+//! instead of asserting just `Strict-Transport-Security` by hand.
+//!
+//! One `ATTACK secure_headers` expands into one `ActionResult` per header
+//! plus a summary row carrying the aggregate [`Grade`].
+
+use crate::expr::Context;
+use crate::report::{ActionResult, Grade};
+
+type Verdict = (&'static str, String); // ("PASS" | "WARN" | "FAIL", detail)
+
+struct HeaderRule {
+    name: &'static str,
+    check: fn(Option<&str>) -> Verdict,
+}
+
+const RULES: &[HeaderRule] = &[
+    HeaderRule { name: "Content-Security-Policy", check: check_csp },
+    HeaderRule { name: "X-Frame-Options", check: check_frame_options },
+    HeaderRule { name: "X-Content-Type-Options", check: check_content_type_options },
+    HeaderRule { name: "Referrer-Policy", check: check_referrer_policy },
+    HeaderRule { name: "Permissions-Policy", check: check_permissions_policy },
+    HeaderRule { name: "Strict-Transport-Security", check: check_hsts },
+];
+
+/// Evaluates the full header matrix against `ctx` (the response an ATTACK
+/// just produced) and returns one row per header plus a summary row.
+pub fn evaluate(ctx: &Context) -> Vec<ActionResult> {
+    let mut rows = Vec::with_capacity(RULES.len() + 1);
+    let (mut passed, mut warned) = (0u32, 0u32);
+
+    for rule in RULES {
+        let header_value = ctx
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(rule.name))
+            .map(|(_, v)| v.as_str());
+        let (verdict, detail) = (rule.check)(header_value);
+        match verdict {
+            "PASS" => passed += 1,
+            "WARN" => warned += 1,
+            _ => {}
+        }
+
+        rows.push(ActionResult {
+            kind: "ATTACK".into(),
+            value: format!("secure_headers:{}", rule.name),
+            target: None,
+            status: if verdict == "FAIL" { "FAILED".into() } else { "SUCCESS".into() },
+            message: format!("[{}] {}", verdict, detail),
+            grade: None,
+        });
+    }
+
+    let grade = grade_from_score(passed, warned, RULES.len() as u32);
+    rows.push(ActionResult {
+        kind: "ATTACK".into(),
+        value: "secure_headers:summary".into(),
+        target: None,
+        status: if grade == Grade::F { "FAILED".into() } else { "SUCCESS".into() },
+        message: format!(
+            "{} passed, {} warned, {} failed out of {} headers",
+            passed,
+            warned,
+            RULES.len() as u32 - passed - warned,
+            RULES.len()
+        ),
+        grade: Some(grade.as_str().to_string()),
+    });
+
+    rows
+}
+
+fn grade_from_score(passed: u32, warned: u32, total: u32) -> Grade {
+    if total == 0 {
+        return Grade::F;
+    }
+    let points = passed * 2 + warned;
+    let max_points = total * 2;
+    let pct = points * 100 / max_points;
+    match pct {
+        90..=100 => Grade::A,
+        75..=89 => Grade::B,
+        60..=74 => Grade::C,
+        40..=59 => Grade::D,
+        _ => Grade::F,
+    }
+}
+
+fn check_csp(value: Option<&str>) -> Verdict {
+    match value {
+        Some(v) if !v.trim().is_empty() => ("PASS", format!("present: {}", v)),
+        _ => ("FAIL", "missing Content-Security-Policy".into()),
+    }
+}
+
+fn check_frame_options(value: Option<&str>) -> Verdict {
+    match value.map(str::to_uppercase) {
+        Some(v) if v == "DENY" || v == "SAMEORIGIN" => ("PASS", format!("present: {}", v)),
+        Some(v) => ("WARN", format!("present but unusual value: {}", v)),
+        None => ("FAIL", "missing X-Frame-Options".into()),
+    }
+}
+
+fn check_content_type_options(value: Option<&str>) -> Verdict {
+    match value {
+        Some(v) if v.eq_ignore_ascii_case("nosniff") => ("PASS", "present: nosniff".into()),
+        Some(v) => ("WARN", format!("present but not 'nosniff': {}", v)),
+        None => ("FAIL", "missing X-Content-Type-Options".into()),
+    }
+}
+
+fn check_referrer_policy(value: Option<&str>) -> Verdict {
+    match value {
+        Some(v) if !v.trim().is_empty() => ("PASS", format!("present: {}", v)),
+        _ => ("WARN", "missing Referrer-Policy".into()),
+    }
+}
+
+fn check_permissions_policy(value: Option<&str>) -> Verdict {
+    match value {
+        Some(v) if !v.trim().is_empty() => ("PASS", format!("present: {}", v)),
+        _ => ("WARN", "missing Permissions-Policy".into()),
+    }
+}
+
+const MIN_HSTS_MAX_AGE: u64 = 15_552_000; // 180 days
+
+fn check_hsts(value: Option<&str>) -> Verdict {
+    let Some(v) = value else {
+        return ("FAIL", "missing Strict-Transport-Security".into());
+    };
+
+    let max_age = v
+        .split(';')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("max-age="))
+        .and_then(|n| n.parse::<u64>().ok());
+    let includes_subdomains = v.to_lowercase().contains("includesubdomains");
+
+    match max_age {
+        Some(age) if age >= MIN_HSTS_MAX_AGE && includes_subdomains => {
+            ("PASS", format!("present: {}", v))
+        }
+        Some(age) if age >= MIN_HSTS_MAX_AGE => {
+            ("WARN", format!("max-age is sufficient but includeSubDomains is missing: {}", v))
+        }
+        Some(_) => ("WARN", format!("max-age is too low: {}", v)),
+        None => ("WARN", format!("present but missing max-age: {}", v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn grade_from_score_cutoffs() {
+        // total = 50 makes max_points = 100, so `warned` alone (passed = 0)
+        // lands exactly on the percentage being tested.
+        assert_eq!(grade_from_score(0, 100, 50), Grade::A);
+        assert_eq!(grade_from_score(0, 90, 50), Grade::A);
+        assert_eq!(grade_from_score(0, 89, 50), Grade::B);
+        assert_eq!(grade_from_score(0, 75, 50), Grade::B);
+        assert_eq!(grade_from_score(0, 74, 50), Grade::C);
+        assert_eq!(grade_from_score(0, 60, 50), Grade::C);
+        assert_eq!(grade_from_score(0, 59, 50), Grade::D);
+        assert_eq!(grade_from_score(0, 40, 50), Grade::D);
+        assert_eq!(grade_from_score(0, 39, 50), Grade::F);
+        assert_eq!(grade_from_score(0, 0, 50), Grade::F);
+    }
+
+    #[test]
+    fn grade_from_score_with_no_rules_is_f() {
+        assert_eq!(grade_from_score(0, 0, 0), Grade::F);
+    }
+
+    #[test]
+    fn csp_rule_requires_a_non_empty_header() {
+        assert_eq!(check_csp(Some("default-src 'self'")).0, "PASS");
+        assert_eq!(check_csp(Some("   ")).0, "FAIL");
+        assert_eq!(check_csp(None).0, "FAIL");
+    }
+
+    #[test]
+    fn frame_options_rule_accepts_deny_or_sameorigin_case_insensitively() {
+        assert_eq!(check_frame_options(Some("deny")).0, "PASS");
+        assert_eq!(check_frame_options(Some("SAMEORIGIN")).0, "PASS");
+        assert_eq!(check_frame_options(Some("allow-from https://x")).0, "WARN");
+        assert_eq!(check_frame_options(None).0, "FAIL");
+    }
+
+    #[test]
+    fn content_type_options_rule_requires_nosniff_case_insensitively() {
+        assert_eq!(check_content_type_options(Some("NOSNIFF")).0, "PASS");
+        assert_eq!(check_content_type_options(Some("nosniff")).0, "PASS");
+        assert_eq!(check_content_type_options(Some("garbage")).0, "WARN");
+        assert_eq!(check_content_type_options(None).0, "FAIL");
+    }
+
+    #[test]
+    fn referrer_and_permissions_policy_rules_only_warn_when_missing() {
+        assert_eq!(check_referrer_policy(Some("no-referrer")).0, "PASS");
+        assert_eq!(check_referrer_policy(None).0, "WARN");
+        assert_eq!(check_permissions_policy(Some("geolocation=()")).0, "PASS");
+        assert_eq!(check_permissions_policy(None).0, "WARN");
+    }
+
+    #[test]
+    fn hsts_rule_requires_both_sufficient_max_age_and_include_subdomains_for_a_pass() {
+        assert_eq!(check_hsts(None).0, "FAIL");
+        assert_eq!(
+            check_hsts(Some("max-age=31536000; includeSubDomains")).0,
+            "PASS"
+        );
+        assert_eq!(
+            check_hsts(Some("max-age=31536000; INCLUDESUBDOMAINS")).0,
+            "PASS"
+        );
+        assert_eq!(check_hsts(Some("max-age=31536000")).0, "WARN");
+        assert_eq!(check_hsts(Some("max-age=100; includeSubDomains")).0, "WARN");
+        assert_eq!(check_hsts(Some("includeSubDomains")).0, "WARN");
+    }
+
+    #[test]
+    fn evaluate_matches_headers_case_insensitively_and_grades_the_summary_row() {
+        let ctx = Context {
+            status: Some(200),
+            headers: HashMap::from([
+                ("content-security-policy".to_string(), "default-src 'self'".to_string()),
+                ("x-frame-options".to_string(), "DENY".to_string()),
+                ("x-content-type-options".to_string(), "nosniff".to_string()),
+                ("referrer-policy".to_string(), "no-referrer".to_string()),
+                ("permissions-policy".to_string(), "geolocation=()".to_string()),
+                (
+                    "strict-transport-security".to_string(),
+                    "max-age=31536000; includeSubDomains".to_string(),
+                ),
+            ]),
+            body: String::new(),
+        };
+
+        let rows = evaluate(&ctx);
+        assert_eq!(rows.len(), RULES.len() + 1);
+        assert!(rows[..RULES.len()].iter().all(|r| r.status == "SUCCESS"));
+
+        let summary = rows.last().unwrap();
+        assert_eq!(summary.value, "secure_headers:summary");
+        assert_eq!(summary.grade.as_deref(), Some("A"));
+        assert_eq!(summary.status, "SUCCESS");
+    }
+
+    #[test]
+    fn evaluate_fails_the_summary_row_when_every_header_is_missing() {
+        let ctx = Context {
+            status: Some(200),
+            headers: HashMap::new(),
+            body: String::new(),
+        };
+
+        let rows = evaluate(&ctx);
+        let summary = rows.last().unwrap();
+        assert_eq!(summary.grade.as_deref(), Some("F"));
+        assert_eq!(summary.status, "FAILED");
+    }
+}
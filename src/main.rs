@@ -8,11 +8,24 @@ use std::path::Path;
 use pest::Parser as PestParser;
 use pest_derive::Parser as PestDeriveParser;
 
-extern crate cdd_core; 
+extern crate cdd_core;
+
+mod engine;
+mod expr;
+mod headers;
+mod openapi;
+mod report;
+mod trust;
+
+use engine::{ActionSpec, EngineConfig, StepSpec};
+use report::{AuditReport, Grade, ReportFormat};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use trust::TrustedEntry;
 
 // Parser Pest definition
 #[derive(PestDeriveParser)]
-#[grammar = "ratel.pest"] 
+#[grammar = "ratel.pest"]
 pub struct RatelParser;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -22,32 +35,9 @@ pub struct RatelConfig {
     pub context: String,
     pub initialized_at: DateTime<Utc>,
     pub customized_at: Option<DateTime<Utc>>,
-    pub expert_hashes: HashMap<String, String>,
-}
-
-// Structures for the audit report consolidated by ratel-cli
-#[derive(Serialize, Clone)]
-struct ActionResult {
-    kind: String,
-    value: String,
-    target: Option<String>,
-    status: String, // "SUCCESS", "FAILED", "ERROR"
-    message: String,
-}
-
-#[derive(Serialize)]
-struct StepResult {
-    title: String,
-    results: Vec<ActionResult>,
-}
-
-#[derive(Serialize)]
-struct AuditReport {
-    name: String,
-    target: String,
-    scope: String,
-    steps: Vec<StepResult>,
-    executed_at: DateTime<Utc>,
+    pub baselines: HashMap<String, TrustedEntry>,
+    #[serde(default)]
+    pub trusted_signers: Vec<String>,
 }
 
 #[derive(ClapParser)]
@@ -63,18 +53,67 @@ enum Commands {
         #[arg(short, long, default_value = "generic")]
         context: String,
     },
-    Check,
-    Certify,
+    Check {
+        /// Criteria every baseline must carry (e.g. `reviewed`, `owasp-baseline`).
+        #[arg(long = "require")]
+        require: Vec<String>,
+    },
+    Certify {
+        /// Criteria to grant the current state of every certified file.
+        #[arg(long = "criteria")]
+        criteria: Vec<String>,
+    },
+    /// Fetches a signed baseline manifest from a trusted registry and merges it in.
+    ///
+    /// Only entries signed by an identity already in `trusted_signers` are
+    /// imported; pin a registry's signer out-of-band with `trust-signer`
+    /// first.
+    Import {
+        url: String,
+    },
+    /// Pins a signer's public key into `trusted_signers` so its baselines
+    /// can be accepted by a future `import`. This is a deliberate,
+    /// out-of-band step: the key must be verified through a channel other
+    /// than the registry itself (e.g. published alongside the registry's
+    /// own release notes) before it's added here.
+    TrustSigner {
+        /// Hex-encoded Ed25519 public key of the signer to trust.
+        signer: String,
+    },
+    /// Generates a `.ratel` scenario from an OpenAPI/Swagger 3 document.
+    ImportOpenapi {
+        spec: String,
+        #[arg(long, default_value = "KERNEL")]
+        scope: String,
+    },
     // Executes the full audit (Parsing -> cdd-core -> JSON Report)
-    Run { path: String },
+    Run {
+        path: String,
+        #[arg(long, value_enum, default_value = "json")]
+        format: ReportFormat,
+        /// Maximum number of STEPs to run concurrently.
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+        /// Per-ATTACK timeout, in seconds, before it is reported as ERROR.
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+        /// Criteria every executed scenario baseline must carry.
+        #[arg(long = "require")]
+        require: Vec<String>,
+        /// Minimum acceptable grade (A-F) for graded attacks like `secure_headers`.
+        #[arg(long = "min-grade")]
+        min_grade: Option<String>,
+    },
 }
 
-fn main() {
+#[tokio::main]
+async fn main() {
     let cli = Cli::parse();
     match &cli.command {
         Commands::Init { context } => {
             println!("🐾 Ratel is sniffing the project structure...");
-            let mut hashes = HashMap::new();
+            let identity = trust::load_or_create_identity();
+            let mut baselines = HashMap::new();
 
             let (p_type, path, content) = if Path::new("package.json").exists() {
                 ("Node.js", "tests/ratel/security.ratel".to_string(), generate_default_scenario())
@@ -83,12 +122,19 @@ fn main() {
             };
 
             setup_security_file(&path, &content);
-            hashes.insert(path, calculate_hash(&content));
-            save_ratel_config(p_type, context, hashes);
+            let hash = calculate_hash(&content);
+            let entry = TrustedEntry::sign(&path, hash, Vec::new(), &identity);
+            baselines.insert(path, entry);
+            save_ratel_config(p_type, context, baselines);
+        },
+        Commands::Check { require } => { check_integrity(require); },
+        Commands::Certify { criteria } => { certify_modifications(criteria); },
+        Commands::Import { url } => { import_baseline(url); },
+        Commands::TrustSigner { signer } => { trust_signer(signer); },
+        Commands::ImportOpenapi { spec, scope } => { import_openapi(spec, scope); },
+        Commands::Run { path, format, jobs, timeout, require, min_grade } => {
+            execute_full_audit(path, *format, *jobs, *timeout, require, min_grade.as_deref()).await;
         },
-        Commands::Check => { check_integrity(); },
-        Commands::Certify => { certify_modifications(); },
-        Commands::Run { path } => { execute_full_audit(path); },
     }
 }
 
@@ -99,21 +145,28 @@ WITH_SCOPE KERNEL
 
 STEP "Secure transport verification"
     ATTACK secure_headers
-    CHECK header "Strict-Transport-Security" EXISTS
-    CHECK response.status BE 200"#
+    CHECK header("Strict-Transport-Security") contains "max-age"
+    CHECK response.status == 200"#
         .to_string()
 }
 
-// Pivot function: Parses the DSL and calls cdd-core for each action
-fn execute_full_audit(path: &str) {
+// Pivot function: Parses the DSL, hands it to the async engine, then prints the report
+async fn execute_full_audit(
+    path: &str,
+    format: ReportFormat,
+    jobs: usize,
+    timeout_secs: u64,
+    require: &[String],
+    min_grade: Option<&str>,
+) {
     // 1. Preliminary integrity check
-    check_integrity();
+    check_integrity(require);
 
     let content = fs::read_to_string(path).expect("Unable to read .ratel file");
-    
+
     // 2. Safe Parsing: avoid thread panic
     let file_parse_result = RatelParser::parse(Rule::file, &content);
-    
+
     let file = match file_parse_result {
         Ok(mut pairs) => pairs.next().unwrap(),
         Err(e) => {
@@ -127,65 +180,69 @@ fn execute_full_audit(path: &str) {
         }
     };
 
-    let mut report = AuditReport {
-        name: String::new(),
-        target: String::new(),
-        scope: String::new(),
-        steps: Vec::new(),
-        executed_at: Utc::now(),
-    };
+    let mut name = String::new();
+    let mut target = String::new();
+    let mut scope = String::new();
+    let mut steps = Vec::new();
 
     for record in file.into_inner() {
         match record.as_rule() {
-            Rule::scenario => report.name = record.into_inner().as_str().replace("\"", ""),
-            Rule::target => report.target = record.into_inner().as_str().replace("\"", ""),
-            Rule::with_scope => report.scope = record.into_inner().as_str().to_string(),
+            Rule::scenario => name = record.into_inner().as_str().replace("\"", ""),
+            Rule::target => target = record.into_inner().as_str().replace("\"", ""),
+            Rule::with_scope => scope = record.into_inner().as_str().to_string(),
             Rule::step => {
                 let mut inner = record.into_inner();
                 let title = inner.next().unwrap().as_str().replace("\"", "");
-                let mut action_results = Vec::new();
-
-                for cmd in inner {
-                    // 3. Synchronizing payloads with cdd-core execution
-                    let result = match cmd.as_rule() {
-                        Rule::attack => {
-                            let attack_val = cmd.into_inner().as_str();
-                            // Call to external cdd-core library
-                            let core_res = cdd_core::execute_attack("attack", attack_val);
-                            
-                            ActionResult {
-                                kind: "ATTACK".into(),
-                                value: attack_val.into(),
-                                target: None,
-                                status: if core_res.success { "SUCCESS".into() } else { "FAILED".into() },
-                                message: core_res.message,
-                            }
-                        },
-                        Rule::check => {
-                            let check_val = cmd.as_str();
-                            // Call to external cdd-core library
-                            let core_res = cdd_core::verify_condition(check_val);
-
-                            ActionResult {
-                                kind: "CHECK".into(),
-                                value: check_val.into(),
-                                target: None,
-                                status: if core_res.success { "SUCCESS".into() } else { "FAILED".into() },
-                                message: core_res.message,
-                            }
-                        },
-                        _ => continue,
-                    };
-                    action_results.push(result);
-                }
-                report.steps.push(StepResult { title, results: action_results });
+                let actions = inner
+                    .filter_map(|cmd| match cmd.as_rule() {
+                        Rule::attack => Some(ActionSpec::Attack(cmd.into_inner().as_str().to_string())),
+                        Rule::check => Some(ActionSpec::Check(cmd.as_str().to_string())),
+                        _ => None,
+                    })
+                    .collect();
+                steps.push(StepSpec { title, actions });
             }
             _ => {}
         }
     }
 
-    // Sends the final JSON report back to cdd-node
-    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    // 3. Cancel in-flight work on Ctrl-C; the audit still emits a partial report.
+    let cancel = CancellationToken::new();
+    let ctrl_c_cancel = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_cancel.cancel();
+        }
+    });
+
+    let config = EngineConfig {
+        jobs,
+        timeout: Duration::from_secs(timeout_secs),
+    };
+    let step_results = engine::run_steps(steps, &config, cancel).await;
+
+    let report = AuditReport {
+        name,
+        target,
+        scope,
+        steps: step_results,
+        executed_at: Utc::now(),
+    };
+
+    // Sends the final report back to cdd-node (or a CI test reporter, for --format junit)
+    let has_failures = report.has_failures();
+    let grade_failure = min_grade.is_some_and(|min| {
+        let Some(min) = Grade::parse(min) else {
+            eprintln!("⚠️  Ignoring --min-grade '{}': not a valid grade (A-F).", min);
+            return false;
+        };
+        report.worst_grade().is_some_and(|worst| worst < min)
+    });
+
+    println!("{}", report.render(format));
+    if has_failures || grade_failure {
+        std::process::exit(1);
+    }
 }
 
 fn calculate_hash(content: &str) -> String {
@@ -202,41 +259,154 @@ fn setup_security_file(path: &str, content: &str) {
     println!("✅ Expert scenario injected into {}.", path);
 }
 
-fn save_ratel_config(p_type: &str, context: &str, hashes: HashMap<String, String>) {
+fn save_ratel_config(p_type: &str, context: &str, baselines: HashMap<String, TrustedEntry>) {
+    let identity = trust::load_or_create_identity();
     let config = RatelConfig {
         version: env!("CARGO_PKG_VERSION").to_string(),
         project_type: p_type.to_string(),
         context: context.to_string(),
         initialized_at: Utc::now(),
         customized_at: None,
-        expert_hashes: hashes,
+        baselines,
+        trusted_signers: vec![hex::encode(identity.verifying_key().to_bytes())],
     };
     let yaml = serde_yaml::to_string(&config).unwrap();
     fs::write("ratel.yaml", yaml).unwrap();
 }
 
-fn check_integrity() {
+fn load_config() -> RatelConfig {
     let config_content = fs::read_to_string("ratel.yaml").expect("Run init first.");
-    let config: RatelConfig = serde_yaml::from_str(&config_content).unwrap();
-    for (path, original_hash) in config.expert_hashes {
-        let current_content = fs::read_to_string(&path).expect("File missing");
-        if calculate_hash(&current_content) != original_hash {
-            panic!("❌ ALERT: '{}' modified! Audit aborted.", path);
-        }
+    serde_yaml::from_str(&config_content).expect("ratel.yaml is malformed")
+}
+
+fn check_integrity(require: &[String]) {
+    let config = load_config();
+    let violations = trust::enforce(&config.baselines, &config.trusted_signers, require);
+    if !violations.is_empty() {
+        let error_report = serde_json::json!({
+            "status": "error",
+            "error_type": "TRUST_VIOLATION",
+            "violations": violations.iter().map(|v| serde_json::json!({
+                "path": v.path,
+                "reason": v.reason,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&error_report).unwrap());
+        std::process::exit(1);
     }
 }
 
-fn certify_modifications() {
-    let config_content = fs::read_to_string("ratel.yaml").expect("No ratel.yaml found.");
-    let mut config: RatelConfig = serde_yaml::from_str(&config_content).unwrap();
-    let mut new_hashes = HashMap::new();
-    for (path, _) in &config.expert_hashes {
+fn certify_modifications(criteria: &[String]) {
+    let mut config = load_config();
+    let identity = trust::load_or_create_identity();
+    let mut new_baselines = HashMap::new();
+
+    for path in config.baselines.keys() {
         if let Ok(content) = fs::read_to_string(path) {
-            new_hashes.insert(path.clone(), calculate_hash(&content));
+            let hash = calculate_hash(&content);
+            let entry = TrustedEntry::sign(path, hash, criteria.to_vec(), &identity);
+            new_baselines.insert(path.clone(), entry);
         }
     }
-    config.expert_hashes = new_hashes;
+
+    config.baselines = new_baselines;
     config.customized_at = Some(Utc::now());
     fs::write("ratel.yaml", serde_yaml::to_string(&config).unwrap()).unwrap();
     println!("New baseline established.");
+}
+
+fn import_baseline(url: &str) {
+    let manifest = match trust::fetch_manifest(url) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let error_report = serde_json::json!({
+                "status": "error",
+                "error_type": "IMPORT_ERROR",
+                "message": e,
+            });
+            println!("{}", serde_json::to_string_pretty(&error_report).unwrap());
+            std::process::exit(1);
+        }
+    };
+
+    let mut config = load_config();
+    let mut imported = 0;
+    for (path, entry) in manifest.entries {
+        if !entry.verify(&path) {
+            eprintln!("⚠️  Skipping '{}': signature does not verify, not importing.", path);
+            continue;
+        }
+        // A manifest entry only proves its signature is internally
+        // consistent — anyone can mint a fresh keypair and self-sign. The
+        // signer must already be pinned (the local identity at `init`, or a
+        // prior `trust-signer`) before an imported baseline is accepted;
+        // otherwise a compromised registry could mint its own trust.
+        if !config.trusted_signers.contains(&entry.signer) {
+            eprintln!(
+                "⚠️  Skipping '{}': signed by untrusted identity {} (not in trusted_signers; run `ratel trust-signer <key>` or add it to ratel.yaml first).",
+                path, entry.signer
+            );
+            continue;
+        }
+        config.baselines.insert(path, entry);
+        imported += 1;
+    }
+
+    fs::write("ratel.yaml", serde_yaml::to_string(&config).unwrap()).unwrap();
+    println!("✅ Imported {} trusted baseline(s) from {}.", imported, url);
+}
+
+fn trust_signer(signer: &str) {
+    if hex::decode(signer).map(|b| b.len()) != Ok(32) {
+        eprintln!("❌ '{}' is not a 32-byte hex-encoded Ed25519 public key.", signer);
+        std::process::exit(1);
+    }
+
+    let mut config = load_config();
+    if config.trusted_signers.contains(&signer.to_string()) {
+        println!("'{}' is already trusted.", signer);
+        return;
+    }
+    config.trusted_signers.push(signer.to_string());
+    fs::write("ratel.yaml", serde_yaml::to_string(&config).unwrap()).unwrap();
+    println!("✅ Now trusting signer {}.", signer);
+}
+
+fn import_openapi(spec_path: &str, scope: &str) {
+    let spec_content = fs::read_to_string(spec_path).expect("Unable to read OpenAPI spec");
+    let spec = match openapi::parse_spec(&spec_content) {
+        Ok(spec) => spec,
+        Err(e) => {
+            let error_report = serde_json::json!({
+                "status": "error",
+                "error_type": "OPENAPI_PARSE_ERROR",
+                "message": e,
+            });
+            println!("{}", serde_json::to_string_pretty(&error_report).unwrap());
+            std::process::exit(1);
+        }
+    };
+
+    let content = openapi::generate_scenario(&spec, scope);
+    let path = "security.ratel".to_string();
+
+    setup_security_file(&path, &content);
+    let hash = calculate_hash(&content);
+
+    let identity = trust::load_or_create_identity();
+    let entry = TrustedEntry::sign(&path, hash, Vec::new(), &identity);
+    // Merge into any existing config instead of overwriting it wholesale —
+    // a project that already ran `init`/`certify`/`import` shouldn't lose
+    // its prior baselines and trusted signers just because it also imports
+    // an OpenAPI-generated scenario.
+    if Path::new("ratel.yaml").exists() {
+        let mut config = load_config();
+        config.baselines.insert(path, entry);
+        config.customized_at = Some(Utc::now());
+        fs::write("ratel.yaml", serde_yaml::to_string(&config).unwrap()).unwrap();
+    } else {
+        let mut baselines = HashMap::new();
+        baselines.insert(path, entry);
+        save_ratel_config("OpenAPI", "generic", baselines);
+    }
 }
\ No newline at end of file
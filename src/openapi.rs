@@ -0,0 +1,118 @@
+//! Generates a `.ratel` scenario from an OpenAPI/Swagger 3 document so
+//! `import-openapi` can bootstrap a realistic audit instead of users
+//! hand-writing the default single-step scenario.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct OpenApiSpec {
+    #[serde(default)]
+    pub servers: Vec<Server>,
+    #[serde(default)]
+    pub paths: HashMap<String, HashMap<String, Operation>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Server {
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Operation {
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub responses: HashMap<String, serde_yaml::Value>,
+}
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Parses an OpenAPI document, sniffing JSON vs YAML since the spec allows
+/// either and the only signal we have is the file content itself.
+pub fn parse_spec(content: &str) -> Result<OpenApiSpec, String> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('{') {
+        serde_json::from_str(content).map_err(|e| format!("invalid OpenAPI JSON: {}", e))
+    } else {
+        serde_yaml::from_str(content).map_err(|e| format!("invalid OpenAPI YAML: {}", e))
+    }
+}
+
+/// Renders the spec as a `.ratel` scenario: one STEP per path/operation,
+/// with CHECKs synthesized from the declared responses.
+pub fn generate_scenario(spec: &OpenApiSpec, scope: &str) -> String {
+    let target = spec
+        .servers
+        .first()
+        .map(|s| s.url.clone())
+        .unwrap_or_else(|| "http://localhost:8080".to_string());
+
+    let mut out = String::new();
+    out.push_str("SCENARIO \"API audit (generated from OpenAPI spec)\"\n");
+    out.push_str(&format!("TARGET \"{}\"\n", target));
+    out.push_str(&format!("WITH_SCOPE {}\n", scope));
+
+    let mut routes: Vec<&String> = spec.paths.keys().collect();
+    routes.sort();
+
+    for route in routes {
+        let operations = &spec.paths[route];
+        let mut methods: Vec<&String> = operations
+            .keys()
+            .filter(|m| HTTP_METHODS.contains(&m.as_str()))
+            .collect();
+        methods.sort();
+
+        for method in methods {
+            let operation = &operations[method];
+            out.push('\n');
+            let title = match &operation.summary {
+                Some(summary) if !summary.trim().is_empty() => {
+                    format!("{} {} — {}", method.to_uppercase(), route, summary.trim())
+                }
+                _ => format!("{} {}", method.to_uppercase(), route),
+            };
+            out.push_str(&format!("STEP \"{}\"\n", title));
+            out.push_str(&format!("    ATTACK http_request \"{} {}\"\n", method.to_uppercase(), route));
+            for check in synthesize_checks(operation) {
+                out.push_str(&format!("    CHECK {}\n", check));
+            }
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Synthesizes the CHECKs for one operation.
+///
+/// The generated `ATTACK http_request` sends one plain, unauthenticated
+/// request — there's no mechanism here to vary credentials per STEP — so it
+/// can only ever land on one documented status. When a spec documents both
+/// an auth-failure code (401/403) and a success code (200/201/204) — the
+/// normal shape for any authenticated endpoint — asserting both against
+/// that single result would be a contradiction. We assert the auth-failure
+/// instead: it's what an unauthenticated call against a properly-secured
+/// endpoint actually returns, and the success check would only ever hold
+/// if auth wasn't enforced at all.
+fn synthesize_checks(operation: &Operation) -> Vec<String> {
+    let mut checks = Vec::new();
+
+    let auth_codes: Vec<&str> = ["401", "403"]
+        .into_iter()
+        .filter(|code| operation.responses.contains_key(*code))
+        .collect();
+
+    if !auth_codes.is_empty() {
+        let clauses: Vec<String> = auth_codes
+            .iter()
+            .map(|code| format!("response.status == {}", code))
+            .collect();
+        checks.push(format!("({})", clauses.join(" OR ")));
+    } else if let Some(code) = ["200", "201", "204"].iter().find(|c| operation.responses.contains_key(**c)) {
+        checks.push(format!("response.status == {}", code));
+    }
+
+    checks
+}
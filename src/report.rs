@@ -0,0 +1,182 @@
+//! The `AuditReport` produced by `ratel run` and its output formats.
+//!
+//! `json` is the native, fully-detailed format; `junit` renders the same
+//! data as a JUnit XML document so CI test reporters (Jenkins, GitLab,
+//! GitHub Actions) can ingest an audit the same way they ingest unit tests.
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct ActionResult {
+    pub kind: String,
+    pub value: String,
+    pub target: Option<String>,
+    pub status: String, // "SUCCESS", "FAILED", "ERROR"
+    pub message: String,
+    // Set only on the summary row of a graded attack (e.g. `secure_headers`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grade: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct StepResult {
+    pub title: String,
+    pub results: Vec<ActionResult>,
+}
+
+#[derive(Serialize)]
+pub struct AuditReport {
+    pub name: String,
+    pub target: String,
+    pub scope: String,
+    pub steps: Vec<StepResult>,
+    pub executed_at: DateTime<Utc>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+}
+
+/// Aggregate score for a graded attack (currently just `secure_headers`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    F,
+    D,
+    C,
+    B,
+    A,
+}
+
+impl Grade {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Grade::A => "A",
+            Grade::B => "B",
+            Grade::C => "C",
+            Grade::D => "D",
+            Grade::F => "F",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Grade> {
+        match s {
+            "A" => Some(Grade::A),
+            "B" => Some(Grade::B),
+            "C" => Some(Grade::C),
+            "D" => Some(Grade::D),
+            "F" => Some(Grade::F),
+            _ => None,
+        }
+    }
+}
+
+impl AuditReport {
+    /// True if any `ActionResult` in the run did not succeed; used to pick
+    /// the process exit code.
+    pub fn has_failures(&self) -> bool {
+        self.steps
+            .iter()
+            .flat_map(|step| &step.results)
+            .any(|action| action.status != "SUCCESS")
+    }
+
+    /// The lowest grade among any graded attacks in the run, if any ran.
+    pub fn worst_grade(&self) -> Option<Grade> {
+        self.steps
+            .iter()
+            .flat_map(|step| &step.results)
+            .filter_map(|action| action.grade.as_deref().and_then(Grade::parse))
+            .min()
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self).unwrap(),
+            ReportFormat::Junit => self.render_junit(),
+        }
+    }
+
+    fn render_junit(&self) -> String {
+        let total_cases: usize = self.steps.iter().map(|s| s.results.len()).collect::<Vec<_>>().iter().sum();
+        let total_failures: usize = self
+            .steps
+            .iter()
+            .flat_map(|s| &s.results)
+            .filter(|a| a.status == "FAILED")
+            .count();
+        let total_errors: usize = self
+            .steps
+            .iter()
+            .flat_map(|s| &s.results)
+            .filter(|a| a.status == "ERROR")
+            .count();
+
+        let mut xml = String::new();
+        xml.push_str(&format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" timestamp=\"{}\">\n",
+            escape_xml(&self.name),
+            total_cases,
+            total_failures,
+            total_errors,
+            self.executed_at.to_rfc3339(),
+        ));
+
+        for step in &self.steps {
+            let failures = step.results.iter().filter(|a| a.status == "FAILED").count();
+            let errors = step.results.iter().filter(|a| a.status == "ERROR").count();
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" timestamp=\"{}\">\n",
+                escape_xml(&step.title),
+                step.results.len(),
+                failures,
+                errors,
+                self.executed_at.to_rfc3339(),
+            ));
+
+            for action in &step.results {
+                let case_name = format!("{} {}", action.kind, action.value);
+                match action.status.as_str() {
+                    "SUCCESS" => {
+                        xml.push_str(&format!(
+                            "    <testcase name=\"{}\" classname=\"{}\"/>\n",
+                            escape_xml(&case_name),
+                            escape_xml(&step.title),
+                        ));
+                    }
+                    "FAILED" => {
+                        xml.push_str(&format!(
+                            "    <testcase name=\"{}\" classname=\"{}\">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                            escape_xml(&case_name),
+                            escape_xml(&step.title),
+                            escape_xml(&action.message),
+                        ));
+                    }
+                    _ => {
+                        xml.push_str(&format!(
+                            "    <testcase name=\"{}\" classname=\"{}\">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                            escape_xml(&case_name),
+                            escape_xml(&step.title),
+                            escape_xml(&action.message),
+                        ));
+                    }
+                }
+            }
+
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
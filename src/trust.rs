@@ -0,0 +1,286 @@
+//! Signed, criteria-based baseline trust model.
+//!
+//! Each certified scenario file in `ratel.yaml` carries more than a SHA256
+//! hash: a set of named criteria (`reviewed`, `safe-to-run`,
+//! `owasp-baseline`, ...) granted by whoever ran `certify`, plus an Ed25519
+//! signature over the path, hash and criteria, keyed to the local author
+//! identity in `.ratel/identity.key` (its public key is stored with each
+//! entry as `signer`). A baseline produced by someone else's `certify` run can
+//! be merged in via `import` and still be trusted, because the signature
+//! travels with it instead of being re-derived locally.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+const IDENTITY_PATH: &str = ".ratel/identity.key";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrustedEntry {
+    pub hash: String,
+    pub criteria: Vec<String>,
+    pub signer: String,    // hex-encoded Ed25519 public key
+    pub signature: String, // hex-encoded Ed25519 signature
+}
+
+impl TrustedEntry {
+    fn signable_message(path: &str, hash: &str, criteria: &[String]) -> Vec<u8> {
+        let mut sorted = criteria.to_vec();
+        sorted.sort();
+        format!("{}:{}:{}", path, hash, sorted.join(",")).into_bytes()
+    }
+
+    pub fn sign(path: &str, hash: String, criteria: Vec<String>, key: &SigningKey) -> Self {
+        let message = Self::signable_message(path, &hash, &criteria);
+        let signature = key.sign(&message);
+        TrustedEntry {
+            hash,
+            criteria,
+            signer: hex::encode(key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+
+    pub fn verify(&self, path: &str) -> bool {
+        let Ok(signer_bytes) = hex::decode(&self.signer) else {
+            return false;
+        };
+        let Ok(signer_arr): Result<[u8; 32], _> = signer_bytes.try_into() else {
+            return false;
+        };
+        let Ok(verifying_key) = VerifyingKey::from_bytes(&signer_arr) else {
+            return false;
+        };
+        let Ok(sig_bytes) = hex::decode(&self.signature) else {
+            return false;
+        };
+        let Ok(sig_arr): Result<[u8; 64], _> = sig_bytes.try_into() else {
+            return false;
+        };
+        let signature = Signature::from_bytes(&sig_arr);
+        let message = Self::signable_message(path, &self.hash, &self.criteria);
+        verifying_key.verify(&message, &signature).is_ok()
+    }
+
+    pub fn has_criteria(&self, required: &str) -> bool {
+        self.criteria.iter().any(|c| c == required)
+    }
+}
+
+/// Loads the local author identity, generating and persisting a fresh
+/// Ed25519 keypair on first use.
+pub fn load_or_create_identity() -> SigningKey {
+    if let Ok(bytes) = fs::read(IDENTITY_PATH) {
+        if let Ok(arr) = <[u8; 32]>::try_from(bytes.as_slice()) {
+            return SigningKey::from_bytes(&arr);
+        }
+    }
+
+    let key = SigningKey::generate(&mut rand::rngs::OsRng);
+    if let Some(parent) = Path::new(IDENTITY_PATH).parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(IDENTITY_PATH, key.to_bytes());
+    key
+}
+
+/// A remote baseline manifest fetched via `ratel import <url>`: a signed
+/// set of `{path, hash, criteria}` entries from a trusted registry.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoteManifest {
+    pub entries: HashMap<String, TrustedEntry>,
+}
+
+pub fn fetch_manifest(url: &str) -> Result<RemoteManifest, String> {
+    if !url.starts_with("https://") {
+        return Err(format!(
+            "refusing to fetch manifest from '{}': registry URL must use https://",
+            url
+        ));
+    }
+
+    let response =
+        reqwest::blocking::get(url).map_err(|e| format!("failed to fetch {}: {}", url, e))?;
+    response
+        .json::<RemoteManifest>()
+        .map_err(|e| format!("invalid baseline manifest from {}: {}", url, e))
+}
+
+pub struct TrustViolation {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Checks that every baseline entry is hash-valid, validly signed, signed by
+/// one of `trusted_signers` (not checked when that list is empty), and grants
+/// every criterion in `required_criteria`. Returns the
+/// violations instead of panicking so the caller can report a structured
+/// error naming exactly which files are missing what.
+pub fn enforce(
+    baselines: &HashMap<String, TrustedEntry>,
+    trusted_signers: &[String],
+    required_criteria: &[String],
+) -> Vec<TrustViolation> {
+    let mut violations = Vec::new();
+
+    for (path, entry) in baselines {
+        let current_content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => {
+                violations.push(TrustViolation {
+                    path: path.clone(),
+                    reason: "file missing".into(),
+                });
+                continue;
+            }
+        };
+
+        if crate::calculate_hash(&current_content) != entry.hash {
+            violations.push(TrustViolation {
+                path: path.clone(),
+                reason: "hash mismatch (file modified since certify)".into(),
+            });
+            continue;
+        }
+
+        if !entry.verify(path) {
+            violations.push(TrustViolation {
+                path: path.clone(),
+                reason: "invalid signature".into(),
+            });
+            continue;
+        }
+
+        if !trusted_signers.is_empty() && !trusted_signers.contains(&entry.signer) {
+            violations.push(TrustViolation {
+                path: path.clone(),
+                reason: format!("signed by untrusted identity {}", entry.signer),
+            });
+            continue;
+        }
+
+        let missing: Vec<&str> = required_criteria
+            .iter()
+            .filter(|c| !entry.has_criteria(c))
+            .map(|s| s.as_str())
+            .collect();
+        if !missing.is_empty() {
+            violations.push(TrustViolation {
+                path: path.clone(),
+                reason: format!("missing criteria: {}", missing.join(", ")),
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `content` to a uniquely-named file under the system temp dir
+    /// and returns its path — `enforce()` re-reads the file from disk, so
+    /// tests that exercise it need a real path, not just an in-memory hash.
+    fn temp_file(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("ratel_trust_test_{}", name));
+        fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn sign_then_verify_round_trips() {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let entry = TrustedEntry::sign("security.ratel", "deadbeef".into(), vec!["reviewed".into()], &key);
+        assert!(entry.verify("security.ratel"));
+    }
+
+    #[test]
+    fn verify_fails_after_hash_tampered_post_signing() {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let mut entry = TrustedEntry::sign("security.ratel", "deadbeef".into(), Vec::new(), &key);
+        entry.hash = "tampered".into();
+        assert!(!entry.verify("security.ratel"));
+    }
+
+    #[test]
+    fn verify_fails_after_criteria_tampered_post_signing() {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let mut entry = TrustedEntry::sign("security.ratel", "deadbeef".into(), vec!["reviewed".into()], &key);
+        entry.criteria.push("owasp-baseline".into());
+        assert!(!entry.verify("security.ratel"));
+    }
+
+    #[test]
+    fn verify_fails_for_a_different_path_than_it_was_signed_for() {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let entry = TrustedEntry::sign("security.ratel", "deadbeef".into(), Vec::new(), &key);
+        assert!(!entry.verify("other.ratel"));
+    }
+
+    #[test]
+    fn enforce_passes_a_valid_untampered_entry() {
+        let path = temp_file("enforce_valid.ratel", "CHECK response.status == 200");
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let hash = crate::calculate_hash("CHECK response.status == 200");
+        let entry = TrustedEntry::sign(&path, hash, vec!["reviewed".into()], &key);
+        let signer = entry.signer.clone();
+
+        let mut baselines = HashMap::new();
+        baselines.insert(path, entry);
+
+        let violations = enforce(&baselines, &[signer], &["reviewed".into()]);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn enforce_reports_hash_mismatch_when_the_file_changed_since_certify() {
+        let path = temp_file("enforce_hash_mismatch.ratel", "CHECK response.status == 200");
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let hash = crate::calculate_hash("CHECK response.status == 200");
+        let entry = TrustedEntry::sign(&path, hash, Vec::new(), &key);
+
+        // The file is modified after certify, so its hash no longer matches.
+        fs::write(&path, "CHECK response.status == 500").unwrap();
+
+        let mut baselines = HashMap::new();
+        baselines.insert(path, entry);
+
+        let violations = enforce(&baselines, &[], &[]);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].reason.contains("hash mismatch"));
+    }
+
+    #[test]
+    fn enforce_reports_an_untrusted_signer() {
+        let path = temp_file("enforce_untrusted_signer.ratel", "CHECK response.status == 200");
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let hash = crate::calculate_hash("CHECK response.status == 200");
+        let entry = TrustedEntry::sign(&path, hash, Vec::new(), &key);
+
+        let mut baselines = HashMap::new();
+        baselines.insert(path, entry);
+
+        let violations = enforce(&baselines, &["some-other-signer".into()], &[]);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].reason.contains("untrusted identity"));
+    }
+
+    #[test]
+    fn enforce_reports_missing_criteria() {
+        let path = temp_file("enforce_missing_criteria.ratel", "CHECK response.status == 200");
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let hash = crate::calculate_hash("CHECK response.status == 200");
+        let entry = TrustedEntry::sign(&path, hash, vec!["reviewed".into()], &key);
+
+        let mut baselines = HashMap::new();
+        baselines.insert(path, entry);
+
+        let violations = enforce(&baselines, &[], &["reviewed".into(), "owasp-baseline".into()]);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].reason.contains("owasp-baseline"));
+    }
+}